@@ -0,0 +1,206 @@
+//! Comparing two SLK581 keys for probable record linkage.
+//!
+//! [`compare`] decodes both keys and reports component-level agreement rather than a single
+//! boolean, so callers can apply their own thresholds. A `2` missing-character marker or a
+//! `999`/`99` placeholder on either side is treated as neutral rather than a hard mismatch, since
+//! it reflects missing data rather than a genuine disagreement.
+
+use decoder::{decode, NameChar, NameSegment, Slk581Record};
+use SLK581Error;
+
+/// Relative weight given to family-name agreement by [`MatchScore::weighted_score`].
+pub const DEFAULT_FAMILY_NAME_WEIGHT: f64 = 0.3;
+/// Relative weight given to given-name agreement by [`MatchScore::weighted_score`].
+pub const DEFAULT_GIVEN_NAME_WEIGHT: f64 = 0.2;
+/// Relative weight given to date-of-birth agreement by [`MatchScore::weighted_score`].
+pub const DEFAULT_DATE_OF_BIRTH_WEIGHT: f64 = 0.4;
+/// Relative weight given to sex agreement by [`MatchScore::weighted_score`].
+pub const DEFAULT_SEX_WEIGHT: f64 = 0.1;
+/// Minimum [`MatchScore::weighted_score`] for [`MatchScore::is_probable_match`] to consider two
+/// keys a match.
+pub const DEFAULT_MATCH_THRESHOLD: f64 = 0.7;
+
+/// The agreement between the same component of two decoded keys.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Agreement {
+    Agree,
+    Disagree,
+    /// There was not enough data on at least one side to compare (e.g. a `999`/`99` placeholder,
+    /// or a name segment with no comparable letters).
+    Unknown,
+}
+
+fn agreement_weight(agreement: Agreement) -> f64 {
+    match agreement {
+        Agreement::Agree => 1.0,
+        Agreement::Unknown => 0.5,
+        Agreement::Disagree => 0.0,
+    }
+}
+
+fn compare_name_segment(a: &NameSegment, b: &NameSegment) -> (Agreement, usize, usize) {
+    let (a_chars, b_chars) = match (a, b) {
+        (&NameSegment::Partial(ref a_chars), &NameSegment::Partial(ref b_chars)) => (a_chars, b_chars),
+        _ => return (Agreement::Unknown, 0, 0),
+    };
+
+    let mut matches = 0;
+    let mut comparable = 0;
+
+    for (a_char, b_char) in a_chars.iter().zip(b_chars.iter()) {
+        match (*a_char, *b_char) {
+            (NameChar::Known(a_c), NameChar::Known(b_c)) => {
+                comparable += 1;
+                if a_c == b_c {
+                    matches += 1;
+                }
+            }
+            // A missing-character marker on either side carries no information, so it is
+            // neither a match nor a mismatch.
+            _ => (),
+        }
+    }
+
+    let agreement = if comparable == 0 {
+        Agreement::Unknown
+    } else if matches == comparable {
+        Agreement::Agree
+    } else {
+        Agreement::Disagree
+    };
+
+    (agreement, matches, comparable)
+}
+
+/// The result of [`compare`]ing two SLK581 keys: component-level agreement, plus a weighted
+/// overall score.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct MatchScore {
+    pub family_name: Agreement,
+    pub given_name: Agreement,
+    pub date_of_birth: Agreement,
+    pub sex: Agreement,
+    /// Matching letters out of `family_name_comparable`.
+    pub family_name_matches: usize,
+    /// Positions in the family name segment where both keys carry a letter.
+    pub family_name_comparable: usize,
+    /// Matching letters out of `given_name_comparable`.
+    pub given_name_matches: usize,
+    /// Positions in the given name segment where both keys carry a letter.
+    pub given_name_comparable: usize,
+}
+
+impl MatchScore {
+    /// Combines the component agreements into a single score in `[0.0, 1.0]`, using
+    /// [`DEFAULT_FAMILY_NAME_WEIGHT`] and its siblings.
+    pub fn weighted_score(&self) -> f64 {
+        agreement_weight(self.family_name) * DEFAULT_FAMILY_NAME_WEIGHT
+            + agreement_weight(self.given_name) * DEFAULT_GIVEN_NAME_WEIGHT
+            + agreement_weight(self.date_of_birth) * DEFAULT_DATE_OF_BIRTH_WEIGHT
+            + agreement_weight(self.sex) * DEFAULT_SEX_WEIGHT
+    }
+
+    /// Convenience threshold check using the default weights: `true` when
+    /// `weighted_score() >= DEFAULT_MATCH_THRESHOLD`.
+    pub fn is_probable_match(&self) -> bool {
+        self.weighted_score() >= DEFAULT_MATCH_THRESHOLD
+    }
+}
+
+fn compare_records(a: &Slk581Record, b: &Slk581Record) -> MatchScore {
+    let (family_name, family_name_matches, family_name_comparable) =
+        compare_name_segment(&a.family_name, &b.family_name);
+    let (given_name, given_name_matches, given_name_comparable) =
+        compare_name_segment(&a.given_name, &b.given_name);
+
+    let date_of_birth = if a.date_of_birth == b.date_of_birth {
+        Agreement::Agree
+    } else {
+        Agreement::Disagree
+    };
+
+    let sex = if a.sex == b.sex {
+        Agreement::Agree
+    } else {
+        Agreement::Disagree
+    };
+
+    MatchScore {
+        family_name: family_name,
+        given_name: given_name,
+        date_of_birth: date_of_birth,
+        sex: sex,
+        family_name_matches: family_name_matches,
+        family_name_comparable: family_name_comparable,
+        given_name_matches: given_name_matches,
+        given_name_comparable: given_name_comparable,
+    }
+}
+
+/// Decodes `a` and `b` and reports their component-level agreement, for deterministic or
+/// probabilistic record linkage.
+///
+/// # Examples
+/// ```
+/// use slk581::compare;
+///
+/// let score = compare("OE2OH191220001", "OE2OH191220001").unwrap();
+/// assert_eq!(score.is_probable_match(), true);
+/// ```
+pub fn compare<'a>(a: &'a str, b: &'a str) -> Result<MatchScore, SLK581Error<'a>> {
+    let record_a = try!(decode(a));
+    let record_b = try!(decode(b));
+
+    Ok(compare_records(&record_a, &record_b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, Agreement};
+
+    #[test]
+    fn it_should_agree_on_identical_keys() {
+        let score = compare("OE2OH191220001", "OE2OH191220001").unwrap();
+        assert_eq!(score.family_name, Agreement::Agree);
+        assert_eq!(score.given_name, Agreement::Agree);
+        assert_eq!(score.date_of_birth, Agreement::Agree);
+        assert_eq!(score.sex, Agreement::Agree);
+        assert_eq!(score.is_probable_match(), true);
+    }
+
+    #[test]
+    fn it_should_disagree_on_a_different_date_of_birth() {
+        let score = compare("OE2OH191220001", "OE2OH200120001").unwrap();
+        assert_eq!(score.date_of_birth, Agreement::Disagree);
+        assert_eq!(score.is_probable_match(), false);
+    }
+
+    #[test]
+    fn it_should_treat_placeholders_as_unknown_rather_than_a_mismatch() {
+        let score = compare("99999191220001", "OE2OH191220001").unwrap();
+        assert_eq!(score.family_name, Agreement::Unknown);
+        assert_eq!(score.given_name, Agreement::Unknown);
+    }
+
+    #[test]
+    fn it_should_treat_missing_character_markers_as_neutral() {
+        // "O22OH..." carries one fewer known letter than "OE2OH...", but the letter both sides
+        // do carry agrees, so a missing-character marker should not turn this into a mismatch.
+        let score = compare("OE2OH191220001", "O22OH191220001").unwrap();
+        assert_eq!(score.family_name, Agreement::Agree);
+        assert_eq!(score.family_name_comparable, 1);
+        assert_eq!(score.family_name_matches, 1);
+    }
+
+    #[test]
+    fn it_should_return_error_for_a_malformed_key() {
+        assert!(compare("tooshort", "OE2OH191220001").is_err());
+    }
+
+    #[test]
+    fn it_should_compare_iso5218_encoded_keys() {
+        let score = compare("OE2OH191220000", "OE2OH191220009").unwrap();
+        assert_eq!(score.sex, Agreement::Disagree);
+        assert_eq!(score.date_of_birth, Agreement::Agree);
+    }
+}
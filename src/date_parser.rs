@@ -0,0 +1,297 @@
+//! Configurable, multi-format date-of-birth parsing.
+//!
+//! [`encode`](../fn.encode.html) accepts only `YYYY-MM-DD`. A [`DateParser`], passed to
+//! [`encode_with`](../fn.encode_with.html), instead tokenizes the input into digit and alphabetic runs, classifies the numeric tokens by
+//! width and value, matches alphabetic tokens against a configurable month-name table, and falls
+//! back to the `dayfirst`/`yearfirst` flags on [`ParserInfo`] to resolve whatever is still
+//! ambiguous.
+
+use chrono::NaiveDate;
+
+use SLK581Error;
+use SLK581Error::InvalidDateOfBirth;
+
+/// Default, English month-name table used by [`ParserInfo::default`].
+///
+/// Both the three-letter abbreviation and the full name are recognized, case-insensitively.
+pub const DEFAULT_MONTH_NAMES: &'static [(&'static str, u32)] = &[
+    ("jan", 1), ("january", 1),
+    ("feb", 2), ("february", 2),
+    ("mar", 3), ("march", 3),
+    ("apr", 4), ("april", 4),
+    ("may", 5),
+    ("jun", 6), ("june", 6),
+    ("jul", 7), ("july", 7),
+    ("aug", 8), ("august", 8),
+    ("sep", 9), ("sept", 9), ("september", 9),
+    ("oct", 10), ("october", 10),
+    ("nov", 11), ("november", 11),
+    ("dec", 12), ("december", 12),
+];
+
+/// Two-digit-year pivot: years below this become `20YY`, years at or above it become `19YY`.
+pub const TWO_DIGIT_YEAR_PIVOT: u32 = 69;
+
+#[derive(Debug, Clone)]
+enum Token {
+    Digits(String),
+    Alpha(String),
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut buf = String::new();
+    let mut buf_is_digit = false;
+
+    for c in input.chars() {
+        let is_digit = c.is_digit(10);
+        let is_alpha = c.is_alphabetic();
+
+        if !is_digit && !is_alpha {
+            if !buf.is_empty() {
+                tokens.push(if buf_is_digit { Token::Digits(buf.clone()) } else { Token::Alpha(buf.clone()) });
+                buf.clear();
+            }
+            continue;
+        }
+
+        if !buf.is_empty() && is_digit != buf_is_digit {
+            tokens.push(if buf_is_digit { Token::Digits(buf.clone()) } else { Token::Alpha(buf.clone()) });
+            buf.clear();
+        }
+
+        buf_is_digit = is_digit;
+        buf.push(c);
+    }
+
+    if !buf.is_empty() {
+        tokens.push(if buf_is_digit { Token::Digits(buf) } else { Token::Alpha(buf) });
+    }
+
+    tokens
+}
+
+fn pivot_two_digit_year(value: u32) -> i32 {
+    if value < TWO_DIGIT_YEAR_PIVOT {
+        2000 + value as i32
+    } else {
+        1900 + value as i32
+    }
+}
+
+/// Parser preferences: which month names to recognize and how to break ties between day, month
+/// and year when the input itself does not disambiguate them.
+#[derive(Debug, Clone)]
+pub struct ParserInfo {
+    /// When two unclassified numeric tokens remain, assign the first one to the day rather than
+    /// the month.
+    pub dayfirst: bool,
+    /// When an unclassified numeric token could be a two-digit year, prefer resolving it before
+    /// day/month instead of after.
+    pub yearfirst: bool,
+    /// Lower-cased month name to month number, e.g. `("dec", 12)`.
+    pub month_names: Vec<(String, u32)>,
+}
+
+impl ParserInfo {
+    /// Builds a `ParserInfo` with the default English month-name table.
+    pub fn new(dayfirst: bool, yearfirst: bool) -> ParserInfo {
+        ParserInfo::with_month_names(dayfirst, yearfirst, Vec::new())
+    }
+
+    /// Builds a `ParserInfo` whose month-name table is the default table plus `extra_month_names`,
+    /// so callers can register localized or additional names without losing the English ones.
+    pub fn with_month_names(dayfirst: bool, yearfirst: bool, extra_month_names: Vec<(String, u32)>) -> ParserInfo {
+        let mut month_names: Vec<(String, u32)> = DEFAULT_MONTH_NAMES.iter()
+            .map(|&(name, month)| (name.to_string(), month))
+            .collect();
+
+        for (name, month) in extra_month_names {
+            month_names.push((name.to_lowercase(), month));
+        }
+
+        ParserInfo { dayfirst: dayfirst, yearfirst: yearfirst, month_names: month_names }
+    }
+
+    fn lookup_month(&self, word: &str) -> Option<u32> {
+        let lc_word = word.to_lowercase();
+        self.month_names.iter()
+            .find(|&&(ref name, _)| *name == lc_word)
+            .map(|&(_, month)| month)
+    }
+}
+
+impl Default for ParserInfo {
+    fn default() -> ParserInfo {
+        ParserInfo::new(false, false)
+    }
+}
+
+/// Parses date-of-birth strings that do not necessarily follow the rigid `YYYY-MM-DD` shape.
+///
+/// # Examples
+///
+/// ```
+/// use slk581::DateParser;
+///
+/// let parser = DateParser::default();
+/// assert!(parser.parse("2000-12-19").is_ok());
+/// assert!(parser.parse("19 Dec 2000").is_ok());
+/// assert!(parser.parse("19/12/00").is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DateParser {
+    info: ParserInfo,
+}
+
+impl DateParser {
+    /// Builds a `DateParser` from the given preferences.
+    pub fn new(info: ParserInfo) -> DateParser {
+        DateParser { info: info }
+    }
+
+    /// Parses `input` into a [`chrono::NaiveDate`], trying the shapes described in the module
+    /// documentation in order.
+    ///
+    /// Returns `InvalidDateOfBirth` when the components cannot be assigned unambiguously or do
+    /// not form a real calendar date.
+    pub fn parse<'a>(&self, input: &str) -> Result<NaiveDate, SLK581Error<'a>> {
+        let mut day: Option<u32> = None;
+        let mut month: Option<u32> = None;
+        let mut year: Option<i32> = None;
+
+        // Numeric tokens that are not yet classified, paired with whether they are still small
+        // enough to possibly be a month (> 12 can only ever be a day or a two-digit year).
+        let mut pending: Vec<(u32, bool)> = Vec::new();
+
+        for token in tokenize(input) {
+            match token {
+                Token::Alpha(word) => {
+                    if month.is_some() {
+                        return Err(InvalidDateOfBirth);
+                    }
+                    month = match self.info.lookup_month(&word) {
+                        Some(m) => Some(m),
+                        None => return Err(InvalidDateOfBirth),
+                    };
+                }
+                Token::Digits(digits) => {
+                    let value: u32 = match digits.parse() {
+                        Ok(v) => v,
+                        Err(_) => return Err(InvalidDateOfBirth),
+                    };
+
+                    if digits.len() == 4 {
+                        if year.is_some() {
+                            return Err(InvalidDateOfBirth);
+                        }
+                        year = Some(value as i32);
+                    } else {
+                        pending.push((value, value <= 12));
+                    }
+                }
+            }
+        }
+
+        let mut remaining = pending.into_iter();
+
+        if self.info.yearfirst && year.is_none() {
+            if let Some((value, _)) = remaining.next() {
+                year = Some(pivot_two_digit_year(value));
+            }
+        }
+
+        for (value, can_be_month) in remaining {
+            if self.info.dayfirst {
+                if day.is_none() { day = Some(value); continue; }
+                if can_be_month && month.is_none() { month = Some(value); continue; }
+            } else {
+                if can_be_month && month.is_none() { month = Some(value); continue; }
+                if day.is_none() { day = Some(value); continue; }
+            }
+
+            if year.is_none() {
+                year = Some(pivot_two_digit_year(value));
+                continue;
+            }
+
+            return Err(InvalidDateOfBirth);
+        }
+
+        match (day, month, year) {
+            (Some(d), Some(m), Some(y)) => {
+                NaiveDate::from_ymd_opt(y, m, d).ok_or(InvalidDateOfBirth)
+            }
+            _ => Err(InvalidDateOfBirth),
+        }
+    }
+}
+
+impl Default for DateParser {
+    fn default() -> DateParser {
+        DateParser::new(ParserInfo::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DateParser, ParserInfo};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn it_should_parse_iso_format() {
+        let parser = DateParser::default();
+        assert_eq!(parser.parse("2000-12-19").unwrap(), NaiveDate::from_ymd(2000, 12, 19));
+    }
+
+    #[test]
+    fn it_should_parse_slash_separated_date() {
+        let parser = DateParser::default();
+        assert_eq!(parser.parse("19/12/2000").unwrap(), NaiveDate::from_ymd(2000, 12, 19));
+    }
+
+    #[test]
+    fn it_should_parse_month_name() {
+        let parser = DateParser::default();
+        assert_eq!(parser.parse("19 Dec 2000").unwrap(), NaiveDate::from_ymd(2000, 12, 19));
+        assert_eq!(parser.parse("December 19, 2000").unwrap(), NaiveDate::from_ymd(2000, 12, 19));
+    }
+
+    #[test]
+    fn it_should_resolve_ambiguous_numeric_date_with_dayfirst() {
+        let parser = DateParser::new(ParserInfo::new(true, false));
+        assert_eq!(parser.parse("19/12/00").unwrap(), NaiveDate::from_ymd(2000, 12, 19));
+    }
+
+    #[test]
+    fn it_should_resolve_ambiguous_numeric_date_without_dayfirst() {
+        let parser = DateParser::new(ParserInfo::new(false, false));
+        assert_eq!(parser.parse("12/19/00").unwrap(), NaiveDate::from_ymd(2000, 12, 19));
+    }
+
+    #[test]
+    fn it_should_apply_two_digit_year_pivot() {
+        let parser = DateParser::default();
+        assert_eq!(parser.parse("19 Dec 99").unwrap(), NaiveDate::from_ymd(1999, 12, 19));
+        assert_eq!(parser.parse("19 Dec 05").unwrap(), NaiveDate::from_ymd(2005, 12, 19));
+    }
+
+    #[test]
+    fn it_should_use_custom_month_names() {
+        let info = ParserInfo::with_month_names(false, false, vec![("dez".to_string(), 12)]);
+        let parser = DateParser::new(info);
+        assert_eq!(parser.parse("19 Dez 2000").unwrap(), NaiveDate::from_ymd(2000, 12, 19));
+    }
+
+    #[test]
+    fn it_should_return_error_for_impossible_date() {
+        let parser = DateParser::default();
+        assert!(parser.parse("30 Feb 1999").is_err());
+    }
+
+    #[test]
+    fn it_should_return_error_when_ambiguous() {
+        let parser = DateParser::default();
+        assert!(parser.parse("12 2000").is_err());
+    }
+}
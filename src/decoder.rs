@@ -0,0 +1,229 @@
+//! Decoding an SLK581 string back into its structured components.
+//!
+//! [`decode`] walks the 14-character key with a small combinator pipeline, in the style of a nom
+//! parser: each step consumes a fixed-width prefix of the remaining input and either returns the
+//! parsed value together with the rest of the string, or a [`SLK581Error`].
+
+use chrono::NaiveDate;
+
+use SLK581Error;
+use SLK581Error::{InvalidDateOfBirth, MalformedKey};
+use {UNKNOWN_FAMILY_NAME, UNKNOWN_GIVEN_NAME, UNKNOWN_CHARACTER_IN_NAME};
+
+/// A single position within a name segment: either a letter carried over from the source name, or
+/// the `2` placeholder marking a character that could not be found.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum NameChar {
+    Known(char),
+    Missing,
+}
+
+/// The decoded `XXX` family name or `ZZ` given name segment of a key.
+#[derive(PartialEq, Debug, Clone)]
+pub enum NameSegment {
+    /// The segment was the `999`/`99` placeholder: no name was available when the key was
+    /// encoded.
+    Unknown,
+    /// The segment carries one [`NameChar`] per position.
+    Partial(Vec<NameChar>),
+}
+
+/// Sex decoded from the trailing digit of a key.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Sex {
+    Male,
+    Female,
+    /// Transgender or not stated; the encoder does not distinguish the two.
+    Unknown,
+    /// [ISO/IEC 5218](https://en.wikipedia.org/wiki/ISO/IEC_5218) "not known" (digit `0`), as
+    /// emitted by [`iso5218_sex_scheme`](../fn.iso5218_sex_scheme.html).
+    NotKnown,
+    /// [ISO/IEC 5218](https://en.wikipedia.org/wiki/ISO/IEC_5218) "not applicable" (digit `9`), as
+    /// emitted by [`iso5218_sex_scheme`](../fn.iso5218_sex_scheme.html).
+    NotApplicable,
+}
+
+/// The structured result of [`decode`]ing an SLK581 key.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Slk581Record {
+    pub family_name: NameSegment,
+    pub given_name: NameSegment,
+    pub date_of_birth: NaiveDate,
+    pub sex: Sex,
+}
+
+fn take<'a>(input: &'a str, n: usize) -> Result<(&'a str, &'a str), SLK581Error<'a>> {
+    if input.len() < n {
+        return Err(MalformedKey);
+    }
+
+    Ok(input.split_at(n))
+}
+
+fn parse_name_segment<'a>(input: &'a str, width: usize, unknown_placeholder: &str) -> Result<(&'a str, NameSegment), SLK581Error<'a>> {
+    let (segment, rest) = try!(take(input, width));
+
+    if segment == unknown_placeholder {
+        return Ok((rest, NameSegment::Unknown));
+    }
+
+    let mut chars = Vec::with_capacity(width);
+    for c in segment.chars() {
+        if c == UNKNOWN_CHARACTER_IN_NAME {
+            chars.push(NameChar::Missing);
+        } else if c.is_ascii_uppercase() {
+            chars.push(NameChar::Known(c));
+        } else {
+            return Err(MalformedKey);
+        }
+    }
+
+    Ok((rest, NameSegment::Partial(chars)))
+}
+
+fn parse_date_of_birth<'a>(input: &'a str) -> Result<(&'a str, NaiveDate), SLK581Error<'a>> {
+    let (segment, rest) = try!(take(input, 8));
+
+    if !segment.chars().all(|c| c.is_ascii_digit()) {
+        return Err(MalformedKey);
+    }
+
+    let day: u32 = segment[0..2].parse().unwrap();
+    let month: u32 = segment[2..4].parse().unwrap();
+    let year: i32 = segment[4..8].parse().unwrap();
+
+    match NaiveDate::from_ymd_opt(year, month, day) {
+        Some(date) => Ok((rest, date)),
+        None => Err(InvalidDateOfBirth),
+    }
+}
+
+fn parse_sex<'a>(input: &'a str) -> Result<(&'a str, Sex), SLK581Error<'a>> {
+    let (segment, rest) = try!(take(input, 1));
+
+    let sex = match segment {
+        "0" => Sex::NotKnown,
+        "1" => Sex::Male,
+        "2" => Sex::Female,
+        "3" => Sex::Unknown,
+        "9" => Sex::NotApplicable,
+        _ => return Err(MalformedKey),
+    };
+
+    Ok((rest, sex))
+}
+
+/// Parses a `XXXZZDDMMYYYYN` key produced by [`encode`](../fn.encode.html) back into its
+/// structured fields.
+///
+/// # Errors
+///
+/// Returns `MalformedKey` when the key is not exactly 14 characters, or contains a character that
+/// cannot be a name letter, a missing-character marker, a digit, or a sex code at that position.
+///
+/// Returns `InvalidDateOfBirth` when the 8-digit date-of-birth group does not form a real calendar
+/// date (e.g. `30021999`).
+///
+/// # Examples
+/// ```
+/// use slk581::{decode, NameChar, NameSegment, Sex};
+///
+/// let record = decode("OE2OH191220001").unwrap();
+/// assert_eq!(record.family_name, NameSegment::Partial(vec![
+///     NameChar::Known('O'), NameChar::Known('E'), NameChar::Missing,
+/// ]));
+/// assert_eq!(record.sex, Sex::Male);
+/// ```
+pub fn decode<'a>(key: &'a str) -> Result<Slk581Record, SLK581Error<'a>> {
+    // Every subsequent step slices `key` by byte count, which only lands on a char boundary when
+    // the whole key is ASCII; reject anything else up front instead of letting `split_at` panic.
+    if key.len() != 14 || !key.is_ascii() {
+        return Err(MalformedKey);
+    }
+
+    let (rest, family_name) = try!(parse_name_segment(key, 3, UNKNOWN_FAMILY_NAME));
+    let (rest, given_name) = try!(parse_name_segment(rest, 2, UNKNOWN_GIVEN_NAME));
+    let (rest, date_of_birth) = try!(parse_date_of_birth(rest));
+    let (rest, sex) = try!(parse_sex(rest));
+
+    if !rest.is_empty() {
+        return Err(MalformedKey);
+    }
+
+    Ok(Slk581Record {
+        family_name: family_name,
+        given_name: given_name,
+        date_of_birth: date_of_birth,
+        sex: sex,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, NameChar, NameSegment, Sex};
+    use chrono::NaiveDate;
+    use SLK581Error::*;
+
+    #[test]
+    fn it_should_decode_happy_path() {
+        let record = decode("OE2OH191220001").unwrap();
+        assert_eq!(record.family_name, NameSegment::Partial(vec![
+            NameChar::Known('O'), NameChar::Known('E'), NameChar::Missing,
+        ]));
+        assert_eq!(record.given_name, NameSegment::Partial(vec![
+            NameChar::Known('O'), NameChar::Known('H'),
+        ]));
+        assert_eq!(record.date_of_birth, NaiveDate::from_ymd(2000, 12, 19));
+        assert_eq!(record.sex, Sex::Male);
+    }
+
+    #[test]
+    fn it_should_decode_unknown_name_placeholders() {
+        let record = decode("99999191220003").unwrap();
+        assert_eq!(record.family_name, NameSegment::Unknown);
+        assert_eq!(record.given_name, NameSegment::Unknown);
+        assert_eq!(record.sex, Sex::Unknown);
+    }
+
+    #[test]
+    fn it_should_return_error_for_wrong_length() {
+        assert_eq!(decode("OE2OH19122000").unwrap_err(), MalformedKey);
+        assert_eq!(decode("OE2OH1912200011").unwrap_err(), MalformedKey);
+    }
+
+    #[test]
+    fn it_should_return_error_for_non_conforming_characters() {
+        assert_eq!(decode("oe2oh191220001").unwrap_err(), MalformedKey);
+        assert_eq!(decode("OE2OH191220004").unwrap_err(), MalformedKey);
+    }
+
+    #[test]
+    fn it_should_return_error_for_impossible_date() {
+        assert_eq!(decode("OE2OH300219991").unwrap_err(), InvalidDateOfBirth);
+    }
+
+    #[test]
+    fn it_should_return_error_instead_of_panicking_on_non_ascii_input() {
+        // 14 bytes, but the 'Ö' is 2 bytes wide, so a byte-length check alone would let this
+        // through and then panic in `str::split_at` on the non-char-boundary index.
+        assert_eq!(decode("aaÖ1920001234").unwrap_err(), MalformedKey);
+    }
+
+    #[test]
+    fn it_should_decode_iso5218_sex_codes() {
+        assert_eq!(decode("OE2OH191220000").unwrap().sex, Sex::NotKnown);
+        assert_eq!(decode("OE2OH191220009").unwrap().sex, Sex::NotApplicable);
+    }
+
+    #[test]
+    fn it_should_round_trip_with_encode() {
+        use encode;
+
+        let date_of_birth: Option<&str> = Some("2000-12-19");
+        let encoded = encode(Some("Doe"), Some("John"), date_of_birth, Some("m")).unwrap();
+        let decoded = decode(encoded.as_str()).unwrap();
+
+        assert_eq!(decoded.date_of_birth, NaiveDate::from_ymd(2000, 12, 19));
+        assert_eq!(decoded.sex, Sex::Male);
+    }
+}
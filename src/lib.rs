@@ -7,13 +7,27 @@
 //! `N` encodes sex.
 
 extern crate chrono;
+extern crate unicode_normalization;
 
-use chrono::NaiveDate;
-use chrono::format::ParseResult;
 use std::error::Error;
 use std::fmt;
 
-use self::SLK581Error::{InvalidDateOfBirth, UnknownDateOfBirth, UnsupportedSex};
+use chrono::NaiveDate;
+use chrono::format::ParseResult;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use self::SLK581Error::{InvalidDateOfBirth, MalformedKey, UnknownDateOfBirth, UnsupportedSex};
+
+mod compare;
+mod date_parser;
+mod decoder;
+mod sex_scheme;
+
+pub use compare::{compare, Agreement, MatchScore};
+pub use date_parser::{DateParser, ParserInfo};
+pub use decoder::{decode, NameChar, NameSegment, Sex, Slk581Record};
+pub use sex_scheme::{default_sex_scheme, iso5218_sex_scheme, SexScheme, TokenSexScheme};
 
 /// Placeholder for unknown family name `999`
 pub const UNKNOWN_FAMILY_NAME: &'static str = "999";
@@ -29,7 +43,8 @@ pub const FEMALE: &'static str = "2";
 pub const TRANSGENDER: &'static str = "3";
 /// Placeholder for unknown sex `3`
 pub const UNKNOWN_SEX: &'static str = "3";
-/// Supported input format of date of birth `YYYY-MM-DD`
+/// Supported input format of date of birth for [`encode`]: `YYYY-MM-DD`. [`encode_with`] accepts
+/// this and other shapes via its [`DateParser`] argument instead.
 pub const INPUT_DATE_FORMAT: &'static str = "%Y-%m-%d";
 /// Output format of date of birth `DDMMYYYY`
 pub const OUTPUT_DATE_FORMAT: &'static str = "%d%m%Y";
@@ -39,6 +54,9 @@ pub enum SLK581Error<'a> {
     InvalidDateOfBirth,
     UnknownDateOfBirth,
     UnsupportedSex(&'a str),
+    /// The key is not a well-formed `XXXZZDDMMYYYYN` string: wrong length, or a character where a
+    /// name letter, missing-character marker, digit or sex code was expected.
+    MalformedKey,
 }
 
 impl<'a> fmt::Display for SLK581Error<'a> {
@@ -56,16 +74,54 @@ impl<'a> Error for SLK581Error<'a> {
             InvalidDateOfBirth => "Unsupported date of birth format.",
             UnknownDateOfBirth => "Unknown date of birth.",
             UnsupportedSex(..) => "Unsupported sex",
+            MalformedKey => "Malformed SLK581 key.",
         }
     }
 }
 
-fn sanitize_name(name: &str) -> String {
+/// Base letters for characters that do not NFKD-decompose into a plain Latin letter plus
+/// combining marks, e.g. `ß` or `Ø`.
+const EXTRA_TRANSLITERATIONS: &'static [(char, &'static str)] = &[
+    ('Ø', "O"), ('ø', "O"),
+    ('Æ', "AE"), ('æ', "AE"),
+    ('Œ', "OE"), ('œ', "OE"),
+    ('Đ', "D"), ('đ', "D"),
+    ('Ł', "L"), ('ł', "L"),
+    ('ß', "SS"),
+];
+
+fn lookup_transliteration(c: char) -> Option<&'static str> {
+    EXTRA_TRANSLITERATIONS.iter()
+        .find(|&&(source, _)| source == c)
+        .map(|&(_, replacement)| replacement)
+}
+
+fn sanitize_name(name: &str, fold_diacritics: bool) -> String {
     let mut buf: String = String::with_capacity(name.len());
 
-    for c in name.chars() {
-        match c {
-            'A' ... 'Z' => buf.push(c),
+    if !fold_diacritics {
+        for c in name.chars() {
+            match c {
+                'A' ... 'Z' => buf.push(c),
+                _ => ()
+            }
+        }
+
+        return buf;
+    }
+
+    for c in name.nfkd() {
+        if is_combining_mark(c) {
+            continue;
+        }
+
+        if let Some(replacement) = lookup_transliteration(c) {
+            buf.push_str(replacement);
+            continue;
+        }
+
+        match c.to_ascii_uppercase() {
+            'A' ... 'Z' => buf.push(c.to_ascii_uppercase()),
             _ => ()
         }
     }
@@ -73,8 +129,8 @@ fn sanitize_name(name: &str) -> String {
     return buf;
 }
 
-fn encode_name(name: Option<&str>, take: usize, vec_pos: Vec<usize>) -> String {
-    let clean_name = sanitize_name(name.unwrap().to_uppercase().as_str());
+fn encode_name(name: Option<&str>, take: usize, vec_pos: Vec<usize>, fold_diacritics: bool) -> String {
+    let clean_name = sanitize_name(name.unwrap().to_uppercase().as_str(), fold_diacritics);
     let mut chars_iter = clean_name.chars().into_iter().take(take);
     let buf_capacity: usize = vec_pos.len();
 
@@ -92,50 +148,48 @@ fn encode_name(name: Option<&str>, take: usize, vec_pos: Vec<usize>) -> String {
         });
 }
 
-fn encode_family_name(family_name: Option<&str>) -> String {
+fn encode_family_name(family_name: Option<&str>, fold_diacritics: bool) -> String {
     if family_name.is_none() {
         return String::from(UNKNOWN_FAMILY_NAME);
     }
 
-    encode_name(family_name, 5, vec![1, 0, 1])
+    encode_name(family_name, 5, vec![1, 0, 1], fold_diacritics)
 }
 
-fn encode_given_name(given_name: Option<&str>) -> String {
+fn encode_given_name(given_name: Option<&str>, fold_diacritics: bool) -> String {
     if given_name.is_none() {
         return String::from(UNKNOWN_GIVEN_NAME);
     }
 
-    encode_name(given_name, 3, vec![1, 0])
+    encode_name(given_name, 3, vec![1, 0], fold_diacritics)
 }
 
-fn encode_date_of_birth<'a>(date_of_birth: Option<&str>) -> Result<String, SLK581Error<'a>> {
+fn encode_date_of_birth_strict<'a>(date_of_birth: Option<&str>) -> Result<String, SLK581Error<'a>> {
     if date_of_birth.is_none() {
         return Err(UnknownDateOfBirth);
     }
 
-    let _date_of_birth: ParseResult<NaiveDate> =
+    let parsed_date: ParseResult<NaiveDate> =
         NaiveDate::parse_from_str(date_of_birth.unwrap(), INPUT_DATE_FORMAT);
 
-    if _date_of_birth.is_err() {
-        return Err(InvalidDateOfBirth);
+    match parsed_date {
+        Ok(date) => Ok(date.format(OUTPUT_DATE_FORMAT).to_string()),
+        Err(_) => Err(InvalidDateOfBirth),
     }
-
-    Ok(_date_of_birth.unwrap().format(OUTPUT_DATE_FORMAT).to_string())
 }
 
-fn encode_sex<'a>(sex: Option<&'a str>) -> Result<String, SLK581Error<'a>> {
-    if sex.is_none() {
-        return Ok(String::from(UNKNOWN_SEX));
+fn encode_date_of_birth<'a>(date_of_birth: Option<&str>, date_parser: &DateParser) -> Result<String, SLK581Error<'a>> {
+    if date_of_birth.is_none() {
+        return Err(UnknownDateOfBirth);
     }
 
-    let _sex = sex.unwrap();
-    let lc_sex = _sex.to_lowercase();
-    match lc_sex.as_str() {
-        "m" | "male" => Ok(String::from(MALE)),
-        "f" | "female" => Ok(String::from(FEMALE)),
-        "t" | "trans" => Ok(String::from(TRANSGENDER)),
-        _ => Err(UnsupportedSex(_sex))
-    }
+    let parsed_date = try!(date_parser.parse(date_of_birth.unwrap()));
+
+    Ok(parsed_date.format(OUTPUT_DATE_FORMAT).to_string())
+}
+
+fn encode_sex<'a>(sex: Option<&'a str>, sex_scheme: &dyn SexScheme) -> Result<String, SLK581Error<'a>> {
+    sex_scheme.encode(sex)
 }
 
 // XXXXXDDMMYYYYN
@@ -220,10 +274,52 @@ pub fn encode<'a>(family_name: Option<&str>,
                   date_of_birth: Option<&str>,
                   sex: Option<&'a str>) -> Result<String, SLK581Error<'a>> {
 
-    let encoded_family_name: String = encode_family_name(family_name);
-    let encoded_given_name: String = encode_given_name(given_name);
-    let encoded_date_of_birth: String = try!(encode_date_of_birth(date_of_birth));
-    let encoded_sex: String = try!(encode_sex(sex));
+    let encoded_family_name: String = encode_family_name(family_name, false);
+    let encoded_given_name: String = encode_given_name(given_name, false);
+    let encoded_date_of_birth: String = try!(encode_date_of_birth_strict(date_of_birth));
+    let encoded_sex: String = try!(encode_sex(sex, &default_sex_scheme()));
+
+    let mut buf = String::with_capacity(14);
+    buf.push_str(encoded_family_name.as_str());
+    buf.push_str(encoded_given_name.as_str());
+    buf.push_str(encoded_date_of_birth.as_str());
+    buf.push_str(encoded_sex.as_str());
+
+    Ok(buf)
+}
+
+/// Same as [`encode`], but lets the caller supply a [`DateParser`] configured with its own
+/// `dayfirst`/`yearfirst` preferences and month-name table, so locales other than `YYYY-MM-DD`
+/// can be accepted; choose whether accented/ligature letters are folded to their closest ASCII
+/// equivalent before encoding (`fold_diacritics`) instead of being silently dropped; and supply a
+/// [`SexScheme`] so a jurisdiction's own sex code set can be encoded instead of the default one.
+///
+/// # Examples
+/// ```
+/// use slk581::{encode_with, DateParser, default_sex_scheme};
+///
+/// let date_of_birth: Option<&str> = Some("19 Dec 2000");
+/// let encoded_result = encode_with(Some("Doe"), Some("John"), date_of_birth, Some("m"),
+///                                   &DateParser::default(), false, &default_sex_scheme());
+/// assert_eq!(encoded_result.unwrap(), "OE2OH191220001");
+///
+/// // "Müller" keeps its "U" once diacritic folding is enabled, instead of dropping it.
+/// let encoded_result = encode_with(Some("Müller"), None, date_of_birth, None,
+///                                   &DateParser::default(), true, &default_sex_scheme());
+/// assert_eq!(encoded_result.unwrap(), "ULE99191220003");
+/// ```
+pub fn encode_with<'a>(family_name: Option<&str>,
+                       given_name: Option<&str>,
+                       date_of_birth: Option<&str>,
+                       sex: Option<&'a str>,
+                       date_parser: &DateParser,
+                       fold_diacritics: bool,
+                       sex_scheme: &dyn SexScheme) -> Result<String, SLK581Error<'a>> {
+
+    let encoded_family_name: String = encode_family_name(family_name, fold_diacritics);
+    let encoded_given_name: String = encode_given_name(given_name, fold_diacritics);
+    let encoded_date_of_birth: String = try!(encode_date_of_birth(date_of_birth, date_parser));
+    let encoded_sex: String = try!(encode_sex(sex, sex_scheme));
 
     let mut buf = String::with_capacity(14);
     buf.push_str(encoded_family_name.as_str());
@@ -263,6 +359,15 @@ mod tests {
         assert_eq!(encoded_result.unwrap(), "99999191220003");
     }
 
+    #[test]
+    fn it_should_reject_dob_formats_only_encode_with_accepts() {
+        // `encode` stays strict YYYY-MM-DD; the lenient formats `DateParser` understands are
+        // only reachable through `encode_with`.
+        let date_of_birth: Option<&str> = Some("19 Dec 2000");
+        let encoded_result: Result<String, SLK581Error> = encode(None, None, date_of_birth, None);
+        assert_eq!(encoded_result.unwrap_err(), InvalidDateOfBirth);
+    }
+
     #[test]
     fn it_should_return_error_for_unsupported_sex() {
         let date_of_birth: Option<&str> = Some("2000-12-19");
@@ -377,4 +482,98 @@ mod tests {
         assert_eq!(encoded_result.is_ok(), true);
         assert_eq!(encoded_result.unwrap(), "BAEOO191220003");
     }
+
+    #[test]
+    fn it_should_encode_with_a_custom_date_parser() {
+        use super::{default_sex_scheme, encode_with, DateParser, ParserInfo};
+
+        let date_of_birth: Option<&str> = Some("19 Dec 2000");
+        let parser = DateParser::new(ParserInfo::default());
+
+        let encoded_result: Result<String, SLK581Error> =
+            encode_with(Some("Doe"), Some("John"), date_of_birth, Some("m"), &parser, false,
+                          &default_sex_scheme());
+        assert_eq!(encoded_result.is_ok(), true);
+        assert_eq!(encoded_result.unwrap(), "OE2OH191220001");
+    }
+
+    #[test]
+    fn it_should_drop_accented_characters_by_default() {
+        use super::{default_sex_scheme, encode_with, DateParser};
+
+        let date_of_birth: Option<&str> = Some("2000-12-19");
+
+        let encoded_result: Result<String, SLK581Error> =
+            encode_with(Some("Müller"), None, date_of_birth, None, &DateParser::default(), false,
+                          &default_sex_scheme());
+        assert_eq!(encoded_result.is_ok(), true);
+        assert_eq!(encoded_result.unwrap(), "LLR99191220003");
+    }
+
+    #[test]
+    fn it_should_fold_accented_characters_when_enabled() {
+        use super::{default_sex_scheme, encode_with, DateParser};
+
+        let date_of_birth: Option<&str> = Some("2000-12-19");
+
+        let encoded_result: Result<String, SLK581Error> =
+            encode_with(Some("Müller"), None, date_of_birth, None, &DateParser::default(), true,
+                          &default_sex_scheme());
+        assert_eq!(encoded_result.is_ok(), true);
+        assert_eq!(encoded_result.unwrap(), "ULE99191220003");
+
+        let encoded_result: Result<String, SLK581Error> =
+            encode_with(None, Some("Renée"), date_of_birth, None, &DateParser::default(), true,
+                          &default_sex_scheme());
+        assert_eq!(encoded_result.is_ok(), true);
+        assert_eq!(encoded_result.unwrap(), "999EN191220003");
+    }
+
+    #[test]
+    fn it_should_fold_ligatures_and_letters_outside_nfkd() {
+        use super::{default_sex_scheme, encode_with, DateParser};
+
+        let date_of_birth: Option<&str> = Some("2000-12-19");
+
+        let encoded_result: Result<String, SLK581Error> =
+            encode_with(Some("Æble"), None, date_of_birth, None, &DateParser::default(), true,
+                          &default_sex_scheme());
+        assert_eq!(encoded_result.is_ok(), true);
+        assert_eq!(encoded_result.unwrap(), "EBE99191220003");
+    }
+
+    #[test]
+    fn it_should_drop_non_transliterable_script_even_when_folding() {
+        use super::{default_sex_scheme, encode_with, DateParser};
+
+        // Cyrillic letters neither NFKD-decompose into Latin nor appear in
+        // `EXTRA_TRANSLITERATIONS`, so `fold_diacritics: true` drops them just like
+        // `fold_diacritics: false` would; only the Latin letters survive.
+        let date_of_birth: Option<&str> = Some("2000-12-19");
+
+        let encoded_result: Result<String, SLK581Error> =
+            encode_with(Some("AЖB"), None, date_of_birth, None, &DateParser::default(), true,
+                          &default_sex_scheme());
+        assert_eq!(encoded_result.is_ok(), true);
+        assert_eq!(encoded_result.unwrap(), "B2299191220003");
+    }
+
+    #[test]
+    fn it_should_encode_with_a_custom_sex_scheme() {
+        use super::{encode_with, iso5218_sex_scheme, DateParser};
+
+        let date_of_birth: Option<&str> = Some("2000-12-19");
+
+        let encoded_result: Result<String, SLK581Error> =
+            encode_with(None, None, date_of_birth, Some("male"), &DateParser::default(), false,
+                          &iso5218_sex_scheme());
+        assert_eq!(encoded_result.is_ok(), true);
+        assert_eq!(encoded_result.unwrap(), "99999191220001");
+
+        let encoded_result: Result<String, SLK581Error> =
+            encode_with(None, None, date_of_birth, None, &DateParser::default(), false,
+                          &iso5218_sex_scheme());
+        assert_eq!(encoded_result.is_ok(), true);
+        assert_eq!(encoded_result.unwrap(), "99999191220000");
+    }
 }
@@ -0,0 +1,110 @@
+//! Pluggable sex-coding schemes.
+//!
+//! `encode_sex` used to bake in a single m/f/t mapping. A [`SexScheme`] maps a set of recognized
+//! input tokens to the single output digit, so callers can encode their jurisdiction's code set
+//! (e.g. [ISO/IEC 5218](https://en.wikipedia.org/wiki/ISO/IEC_5218)) instead of the default one.
+
+use SLK581Error;
+use SLK581Error::UnsupportedSex;
+
+/// Maps recognized sex tokens to the single output digit carried by the trailing `N` of a key.
+pub trait SexScheme {
+    /// Encodes `sex` into the single digit this scheme assigns it.
+    ///
+    /// Returns `UnsupportedSex` when `sex` is `Some` but not a token this scheme recognizes.
+    fn encode<'a>(&self, sex: Option<&'a str>) -> Result<String, SLK581Error<'a>>;
+}
+
+/// A data-driven [`SexScheme`]: a default code for `None`, plus an ordered list of
+/// (case-insensitive token, code) pairs checked in registration order.
+pub struct TokenSexScheme {
+    default_code: String,
+    tokens: Vec<(String, String)>,
+}
+
+impl TokenSexScheme {
+    /// Builds an empty scheme that encodes `None` as `default_code`.
+    pub fn new(default_code: &str) -> TokenSexScheme {
+        TokenSexScheme { default_code: default_code.to_string(), tokens: Vec::new() }
+    }
+
+    /// Registers an additional recognized token, matched case-insensitively, that encodes to
+    /// `code`.
+    pub fn with_token(mut self, token: &str, code: &str) -> TokenSexScheme {
+        self.tokens.push((token.to_lowercase(), code.to_string()));
+        self
+    }
+}
+
+impl SexScheme for TokenSexScheme {
+    fn encode<'a>(&self, sex: Option<&'a str>) -> Result<String, SLK581Error<'a>> {
+        let input = match sex {
+            None => return Ok(self.default_code.clone()),
+            Some(s) => s,
+        };
+
+        let lc_input = input.to_lowercase();
+        for &(ref token, ref code) in self.tokens.iter() {
+            if *token == lc_input {
+                return Ok(code.clone());
+            }
+        }
+
+        Err(UnsupportedSex(input))
+    }
+}
+
+/// The crate's original scheme: `m`/`male` -> `1`, `f`/`female` -> `2`, `t`/`trans` or not stated
+/// -> `3`.
+pub fn default_sex_scheme() -> TokenSexScheme {
+    TokenSexScheme::new(::UNKNOWN_SEX)
+        .with_token("m", ::MALE).with_token("male", ::MALE)
+        .with_token("f", ::FEMALE).with_token("female", ::FEMALE)
+        .with_token("t", ::TRANSGENDER).with_token("trans", ::TRANSGENDER)
+}
+
+/// [ISO/IEC 5218](https://en.wikipedia.org/wiki/ISO/IEC_5218): `0` not known (also the default for
+/// `None`), `1` male, `2` female, `9` not applicable. The standard has no separate
+/// indeterminate/intersex code; register a custom [`TokenSexScheme`] if your dataset needs one.
+pub fn iso5218_sex_scheme() -> TokenSexScheme {
+    TokenSexScheme::new("0")
+        .with_token("m", "1").with_token("male", "1")
+        .with_token("f", "2").with_token("female", "2")
+        .with_token("not known", "0").with_token("unknown", "0")
+        .with_token("not applicable", "9").with_token("n/a", "9")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_sex_scheme, iso5218_sex_scheme, SexScheme, TokenSexScheme};
+    use SLK581Error::UnsupportedSex;
+
+    #[test]
+    fn it_should_encode_the_default_scheme() {
+        let scheme = default_sex_scheme();
+        assert_eq!(scheme.encode(None).unwrap(), "3");
+        assert_eq!(scheme.encode(Some("M")).unwrap(), "1");
+        assert_eq!(scheme.encode(Some("female")).unwrap(), "2");
+        assert_eq!(scheme.encode(Some("trans")).unwrap(), "3");
+        assert_eq!(scheme.encode(Some("x")).unwrap_err(), UnsupportedSex("x"));
+    }
+
+    #[test]
+    fn it_should_encode_iso5218() {
+        let scheme = iso5218_sex_scheme();
+        assert_eq!(scheme.encode(None).unwrap(), "0");
+        assert_eq!(scheme.encode(Some("male")).unwrap(), "1");
+        assert_eq!(scheme.encode(Some("F")).unwrap(), "2");
+        assert_eq!(scheme.encode(Some("Not Applicable")).unwrap(), "9");
+        assert_eq!(scheme.encode(Some("t")).unwrap_err(), UnsupportedSex("t"));
+    }
+
+    #[test]
+    fn it_should_register_extra_synonyms_without_rejecting_valid_input() {
+        let scheme = TokenSexScheme::new("9")
+            .with_token("m", "1")
+            .with_token("hombre", "1");
+
+        assert_eq!(scheme.encode(Some("hombre")).unwrap(), "1");
+    }
+}
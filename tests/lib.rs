@@ -1,6 +1,9 @@
 extern crate slk581;
 
 use slk581::encode;
+use slk581::{default_sex_scheme, encode_with, iso5218_sex_scheme, DateParser};
+use slk581::{decode, NameSegment, Sex};
+use slk581::{compare, Agreement};
 use slk581::SLK581Error;
 use slk581::SLK581Error::*;
 
@@ -27,6 +30,13 @@ fn slk581_should_encode_dob() {
     assert_eq!(encoded_result.unwrap(), "99999191220003");
 }
 
+#[test]
+fn slk581_should_reject_dob_formats_only_encode_with_accepts() {
+    let date_of_birth: Option<&str> = Some("19 Dec 2000");
+    let encoded_result: Result<String, SLK581Error> = encode(None, None, date_of_birth, None);
+    assert_eq!(encoded_result.unwrap_err(), InvalidDateOfBirth);
+}
+
 #[test]
 fn slk581_should_return_error_for_unsupported_sex() {
     let date_of_birth: Option<&str> = Some("2000-12-19");
@@ -141,3 +151,95 @@ fn slk581_should_encode_happy_path() {
     assert_eq!(encoded_result.is_ok(), true);
     assert_eq!(encoded_result.unwrap(), "BAEOO191220003");
 }
+
+#[test]
+fn slk581_should_encode_with_a_custom_date_parser() {
+    let date_of_birth: Option<&str> = Some("19/12/00");
+    let parser = DateParser::default();
+
+    let encoded_result: Result<String, SLK581Error> =
+        encode_with(Some("Doe"), Some("John"), date_of_birth, Some("m"), &parser, false,
+                      &default_sex_scheme());
+    assert_eq!(encoded_result.is_ok(), true);
+    assert_eq!(encoded_result.unwrap(), "OE2OH191220001");
+}
+
+#[test]
+fn slk581_should_round_trip_encode_and_decode() {
+    let date_of_birth: Option<&str> = Some("2000-12-19");
+    let encoded = encode(Some("Doe"), Some("John"), date_of_birth, Some("m")).unwrap();
+
+    let record = decode(encoded.as_str()).unwrap();
+    assert_eq!(record.sex, Sex::Male);
+    assert_ne!(record.family_name, NameSegment::Unknown);
+}
+
+#[test]
+fn slk581_should_return_error_for_malformed_key() {
+    assert_eq!(decode("tooshort").unwrap_err(), MalformedKey);
+}
+
+#[test]
+fn slk581_should_return_error_instead_of_panicking_on_non_ascii_key() {
+    assert_eq!(decode("aaÖ1920001234").unwrap_err(), MalformedKey);
+}
+
+#[test]
+fn slk581_should_drop_accented_characters_by_default() {
+    let date_of_birth: Option<&str> = Some("2000-12-19");
+
+    let encoded_result: Result<String, SLK581Error> =
+        encode_with(Some("Müller"), None, date_of_birth, None, &DateParser::default(), false,
+                      &default_sex_scheme());
+    assert_eq!(encoded_result.is_ok(), true);
+    assert_eq!(encoded_result.unwrap(), "LLR99191220003");
+}
+
+#[test]
+fn slk581_should_fold_accented_characters_when_enabled() {
+    let date_of_birth: Option<&str> = Some("2000-12-19");
+
+    let encoded_result: Result<String, SLK581Error> =
+        encode_with(Some("Müller"), None, date_of_birth, None, &DateParser::default(), true,
+                      &default_sex_scheme());
+    assert_eq!(encoded_result.is_ok(), true);
+    assert_eq!(encoded_result.unwrap(), "ULE99191220003");
+}
+
+#[test]
+fn slk581_should_drop_non_transliterable_script_even_when_folding() {
+    let date_of_birth: Option<&str> = Some("2000-12-19");
+
+    let encoded_result: Result<String, SLK581Error> =
+        encode_with(Some("AЖB"), None, date_of_birth, None, &DateParser::default(), true,
+                      &default_sex_scheme());
+    assert_eq!(encoded_result.is_ok(), true);
+    assert_eq!(encoded_result.unwrap(), "B2299191220003");
+}
+
+#[test]
+fn slk581_should_encode_with_a_custom_sex_scheme() {
+    let date_of_birth: Option<&str> = Some("2000-12-19");
+
+    let encoded_result: Result<String, SLK581Error> =
+        encode_with(None, None, date_of_birth, None, &DateParser::default(), false,
+                      &iso5218_sex_scheme());
+    assert_eq!(encoded_result.is_ok(), true);
+    assert_eq!(encoded_result.unwrap(), "99999191220000");
+}
+
+#[test]
+fn slk581_should_compare_two_keys() {
+    let date_of_birth: Option<&str> = Some("2000-12-19");
+    let key_a = encode(Some("Doe"), Some("John"), date_of_birth, Some("m")).unwrap();
+    let key_b = encode(Some("Doe"), Some("John"), date_of_birth, Some("m")).unwrap();
+
+    let score = compare(key_a.as_str(), key_b.as_str()).unwrap();
+    assert_eq!(score.is_probable_match(), true);
+}
+
+#[test]
+fn slk581_should_compare_iso5218_encoded_keys() {
+    let score = compare("OE2OH191220000", "OE2OH191220009").unwrap();
+    assert_eq!(score.date_of_birth, Agreement::Agree);
+}